@@ -2,19 +2,47 @@ use bitflags::bitflags;
 use std::fmt;
 
 const VRAM_SIZE: usize = 8 * 1024;
+const VRAM_BANKS: usize = 2;
+const OAM_SIZE: usize = 0xa0;
+const PALETTE_RAM_SIZE: usize = 64;
 const SCREEN_WIDTH: u8 = 160;
 const SCREEN_HEIGHT: u8 = 144;
+const FRAMEBUFFER_SIZE: usize = SCREEN_WIDTH as usize * SCREEN_HEIGHT as usize;
 
 pub struct PPU {
-    vram: [u8; VRAM_SIZE],
+    vram: [[u8; VRAM_SIZE]; VRAM_BANKS],
+    vbk: u8,
+    oam: [u8; OAM_SIZE],
     mode: Mode,
     bgp: u8,
+    obp0: u8,
+    obp1: u8,
+    cgb: bool,
+    bg_palette_ram: [u8; PALETTE_RAM_SIZE],
+    obj_palette_ram: [u8; PALETTE_RAM_SIZE],
+    bg_palette_index: u8,
+    obj_palette_index: u8,
     clocks: u32,
     ly: u8,
+    lyc: u8,
+    lyc_flag: bool,
     stat: Stat,
     scy: u8,
     scx: u8,
+    wy: u8,
+    wx: u8,
+    window_line: u8,
     control: Control,
+    framebuffer: [u8; FRAMEBUFFER_SIZE],
+    color_framebuffer: [u16; FRAMEBUFFER_SIZE],
+    bg_priority_line: [bool; SCREEN_WIDTH as usize],
+    bg_color_index_line: [u8; SCREEN_WIDTH as usize],
+    dma: u8,
+    dma_source: u16,
+    dma_progress: u8,
+    dma_active: bool,
+    dma_clocks: u32,
+    interrupts: u8,
 }
 
 bitflags!(
@@ -52,18 +80,455 @@ enum Mode {
     AccessVRAM,
 }
 
+impl Default for PPU {
+    fn default() -> PPU {
+        PPU::new()
+    }
+}
+
 impl PPU {
     pub fn new() -> PPU {
+        Self::new_internal(false)
+    }
+
+    /// Game Boy Color mode: banked VRAM, CGB palette RAM and BG tile attributes.
+    pub fn new_cgb() -> PPU {
+        Self::new_internal(true)
+    }
+
+    fn new_internal(cgb: bool) -> PPU {
         PPU {
-            vram: [0; VRAM_SIZE],
+            vram: [[0; VRAM_SIZE]; VRAM_BANKS],
+            vbk: 0,
+            oam: [0; OAM_SIZE],
             mode: Mode::HBlank,
             bgp: 0,
+            obp0: 0,
+            obp1: 0,
+            cgb,
+            bg_palette_ram: [0; PALETTE_RAM_SIZE],
+            obj_palette_ram: [0; PALETTE_RAM_SIZE],
+            bg_palette_index: 0,
+            obj_palette_index: 0,
             clocks: 0,
             ly: 0,
+            lyc: 0,
+            lyc_flag: false,
             stat: Stat::empty(),
             scy: 0,
             scx: 0,
+            wy: 0,
+            wx: 0,
+            window_line: 0,
             control: Control::empty(),
+            framebuffer: [0; FRAMEBUFFER_SIZE],
+            color_framebuffer: [0; FRAMEBUFFER_SIZE],
+            bg_priority_line: [false; SCREEN_WIDTH as usize],
+            bg_color_index_line: [0; SCREEN_WIDTH as usize],
+            dma: 0,
+            dma_source: 0,
+            dma_progress: 0,
+            dma_active: false,
+            dma_clocks: 0,
+            interrupts: 0,
+        }
+    }
+
+    /// Pending interrupts raised since the last call, bit0 = VBlank, bit1 = STAT.
+    /// Clears the pending bits on read, like the real IF register does for these sources.
+    pub fn take_interrupts(&mut self) -> u8 {
+        let interrupts = self.interrupts;
+        self.interrupts = 0;
+        interrupts
+    }
+
+    fn fire_vblank(&mut self) {
+        self.interrupts |= 0b0000_0001;
+    }
+
+    fn fire_stat(&mut self) {
+        self.interrupts |= 0b0000_0010;
+    }
+
+    fn set_ly(&mut self, ly: u8) {
+        self.ly = ly;
+
+        let coincidence = self.ly == self.lyc;
+        if coincidence && !self.lyc_flag && self.stat.contains(Stat::LYC_INTERRUPT) {
+            self.fire_stat();
+        }
+        self.lyc_flag = coincidence;
+    }
+
+    /// Address of the next OAM DMA source byte the bus needs to feed in via
+    /// `oam_dma_feed`, if a transfer is in flight and the next byte is due.
+    pub fn oam_dma_request(&self) -> Option<u16> {
+        if self.dma_active && self.dma_clocks >= 4 {
+            Some(self.dma_source + self.dma_progress as u16)
+        } else {
+            None
+        }
+    }
+
+    /// Called by the bus with the byte read from the address `oam_dma_request` returned.
+    pub fn oam_dma_feed(&mut self, v: u8) {
+        if !self.dma_active {
+            return;
+        }
+
+        self.oam[self.dma_progress as usize] = v;
+        self.dma_progress += 1;
+        self.dma_clocks -= 4;
+
+        if self.dma_progress as usize >= OAM_SIZE {
+            self.dma_active = false;
+        }
+    }
+
+    /// Classic 2-bit-shade framebuffer. Populated in DMG mode; left untouched in CGB mode
+    /// where `frame_rgb555` carries the real output instead.
+    pub fn frame(&self) -> &[u8] {
+        &self.framebuffer
+    }
+
+    /// 15-bit RGB555 framebuffer (bank bits unused, bit15 clear), only meaningful in CGB mode.
+    pub fn frame_rgb555(&self) -> &[u16] {
+        &self.color_framebuffer
+    }
+
+    fn render_scanline(&mut self) {
+        if self.cgb {
+            self.render_scanline_cgb();
+        } else {
+            self.render_scanline_dmg();
+        }
+    }
+
+    fn render_scanline_dmg(&mut self) {
+        let bg_tile_map_base: u16 = if self.control.contains(Control::BG_TILE_MAP) {
+            0x9c00
+        } else {
+            0x9800
+        };
+        let window_tile_map_base: u16 = if self.control.contains(Control::WINDOW_TILE_MAP) {
+            0x9c00
+        } else {
+            0x9800
+        };
+        let signed_tile_data = !self.control.contains(Control::BG_WINDOW_TILE);
+        let window_active =
+            self.control.contains(Control::WINDOW_ENABLE) && self.ly >= self.wy;
+        let mut window_used = false;
+
+        for x in 0..SCREEN_WIDTH {
+            let (color_index, shade) = if !self.control.contains(Control::BG_ENABLE) {
+                (0, 0)
+            } else if window_active && x as u16 + 7 >= self.wx as u16 {
+                window_used = true;
+
+                let tile_y = self.window_line;
+                let tile_x = (x as u16 + 7 - self.wx as u16) as u8;
+
+                self.fetch_shade(window_tile_map_base, signed_tile_data, tile_x, tile_y)
+            } else {
+                let tile_y = self.ly.wrapping_add(self.scy);
+                let tile_x = x.wrapping_add(self.scx);
+
+                self.fetch_shade(bg_tile_map_base, signed_tile_data, tile_x, tile_y)
+            };
+
+            self.framebuffer[self.ly as usize * SCREEN_WIDTH as usize + x as usize] = shade;
+            self.bg_color_index_line[x as usize] = color_index;
+        }
+
+        if window_used {
+            self.window_line = self.window_line.wrapping_add(1);
+        }
+    }
+
+    /// Looks up the tile at (tile_x, tile_y) in the given tile map and returns its
+    /// (raw 2-bit color index, final BGP-mapped shade). Shared by background and
+    /// window, which only differ in which tile map and which (x, y) they index into it.
+    fn fetch_shade(&self, tile_map_base: u16, signed_tile_data: bool, tile_x: u8, tile_y: u8) -> (u8, u8) {
+        let tile_map_addr = tile_map_base + (tile_y as u16 / 8) * 32 + (tile_x as u16 / 8);
+        let tile_index = self.vram[0][(tile_map_addr & (VRAM_SIZE - 1) as u16) as usize];
+
+        let tile_data_addr = if signed_tile_data {
+            (0x9000_i32 + (tile_index as i8 as i32) * 16) as u16
+        } else {
+            0x8000 + (tile_index as u16) * 16
+        };
+
+        let row = (tile_y % 8) as u16;
+        let low = self.vram[0][((tile_data_addr + row * 2) & (VRAM_SIZE - 1) as u16) as usize];
+        let high = self.vram[0][((tile_data_addr + row * 2 + 1) & (VRAM_SIZE - 1) as u16) as usize];
+
+        let bit = 7 - (tile_x % 8);
+        let color_index = (((high >> bit) & 1) << 1) | ((low >> bit) & 1);
+
+        (color_index, (self.bgp >> (color_index * 2)) & 0b11)
+    }
+
+    fn render_scanline_cgb(&mut self) {
+        let bg_tile_map_base: u16 = if self.control.contains(Control::BG_TILE_MAP) {
+            0x9c00
+        } else {
+            0x9800
+        };
+        let window_tile_map_base: u16 = if self.control.contains(Control::WINDOW_TILE_MAP) {
+            0x9c00
+        } else {
+            0x9800
+        };
+        let signed_tile_data = !self.control.contains(Control::BG_WINDOW_TILE);
+        let window_active =
+            self.control.contains(Control::WINDOW_ENABLE) && self.ly >= self.wy;
+        let mut window_used = false;
+
+        for x in 0..SCREEN_WIDTH {
+            let (tile_map_base, tile_x, tile_y) =
+                if window_active && x as u16 + 7 >= self.wx as u16 {
+                    window_used = true;
+                    (
+                        window_tile_map_base,
+                        (x as u16 + 7 - self.wx as u16) as u8,
+                        self.window_line,
+                    )
+                } else {
+                    (
+                        bg_tile_map_base,
+                        x.wrapping_add(self.scx),
+                        self.ly.wrapping_add(self.scy),
+                    )
+                };
+
+            let (color_index, color, bg_priority) =
+                self.fetch_color(tile_map_base, signed_tile_data, tile_x, tile_y);
+
+            let index = self.ly as usize * SCREEN_WIDTH as usize + x as usize;
+            self.color_framebuffer[index] = color;
+            self.bg_color_index_line[x as usize] = color_index;
+            self.bg_priority_line[x as usize] = bg_priority;
+        }
+
+        if window_used {
+            self.window_line = self.window_line.wrapping_add(1);
+        }
+    }
+
+    /// CGB counterpart to `fetch_shade`: reads the tile-map attribute byte from VRAM
+    /// bank 1 alongside the tile index in bank 0, applies its flips/bank/palette bits,
+    /// and returns (raw 2-bit color index, final RGB555 color, BG-over-OBJ priority).
+    fn fetch_color(
+        &self,
+        tile_map_base: u16,
+        signed_tile_data: bool,
+        tile_x: u8,
+        tile_y: u8,
+    ) -> (u8, u16, bool) {
+        let tile_map_addr = tile_map_base + (tile_y as u16 / 8) * 32 + (tile_x as u16 / 8);
+        let map_offset = (tile_map_addr & (VRAM_SIZE - 1) as u16) as usize;
+
+        let tile_index = self.vram[0][map_offset];
+        let attrs = self.vram[1][map_offset];
+
+        let palette = (attrs & 0b0000_0111) as usize;
+        let tile_bank = ((attrs >> 3) & 1) as usize;
+        let x_flip = attrs & 0b0010_0000 != 0;
+        let y_flip = attrs & 0b0100_0000 != 0;
+        let bg_priority = attrs & 0b1000_0000 != 0;
+
+        let tile_data_addr = if signed_tile_data {
+            (0x9000_i32 + (tile_index as i8 as i32) * 16) as u16
+        } else {
+            0x8000 + (tile_index as u16) * 16
+        };
+
+        let mut row = (tile_y % 8) as u16;
+        if y_flip {
+            row = 7 - row;
+        }
+
+        let low = self.vram[tile_bank][((tile_data_addr + row * 2) & (VRAM_SIZE - 1) as u16) as usize];
+        let high =
+            self.vram[tile_bank][((tile_data_addr + row * 2 + 1) & (VRAM_SIZE - 1) as u16) as usize];
+
+        let bit = if x_flip { tile_x % 8 } else { 7 - (tile_x % 8) };
+        let color_index = (((high >> bit) & 1) << 1) | ((low >> bit) & 1);
+
+        (color_index, self.bg_palette_color(palette, color_index as usize), bg_priority)
+    }
+
+    fn bg_palette_color(&self, palette: usize, color_index: usize) -> u16 {
+        Self::palette_color(&self.bg_palette_ram, palette, color_index)
+    }
+
+    fn obj_palette_color(&self, palette: usize, color_index: usize) -> u16 {
+        Self::palette_color(&self.obj_palette_ram, palette, color_index)
+    }
+
+    fn palette_color(palette_ram: &[u8; PALETTE_RAM_SIZE], palette: usize, color_index: usize) -> u16 {
+        let offset = palette * 8 + color_index * 2;
+        (palette_ram[offset] as u16 | ((palette_ram[offset + 1] as u16) << 8)) & 0x7fff
+    }
+
+    fn render_sprites(&mut self) {
+        if self.cgb {
+            self.render_sprites_cgb();
+        } else {
+            self.render_sprites_dmg();
+        }
+    }
+
+    /// Sprites covering the current scanline, up to the hardware's 10-per-line cap,
+    /// in ascending OAM index order.
+    fn sprites_on_line(&self, height: i16) -> Vec<usize> {
+        let mut sprites = Vec::new();
+        for i in 0..40 {
+            let y = self.oam[i * 4] as i16 - 16;
+            if self.ly as i16 - y >= 0 && self.ly as i16 - y < height {
+                sprites.push(i);
+                if sprites.len() == 10 {
+                    break;
+                }
+            }
+        }
+        sprites
+    }
+
+    fn render_sprites_dmg(&mut self) {
+        if !self.control.contains(Control::OBJ_ENABLE) {
+            return;
+        }
+
+        let height: i16 = if self.control.contains(Control::OBJ_SIZE) {
+            16
+        } else {
+            8
+        };
+
+        let mut sprites = self.sprites_on_line(height);
+        // lower X (then lower OAM index) wins, so draw those last to win the overwrite
+        sprites.sort_by_key(|&i| self.oam[i * 4 + 1]);
+
+        for &i in sprites.iter().rev() {
+            let entry = i * 4;
+            let y = self.oam[entry] as i16 - 16;
+            let x = self.oam[entry + 1] as i16 - 8;
+            let mut tile_index = self.oam[entry + 2];
+            let attrs = self.oam[entry + 3];
+
+            if height == 16 {
+                tile_index &= 0xfe;
+            }
+
+            let y_flip = attrs & 0b0100_0000 != 0;
+            let x_flip = attrs & 0b0010_0000 != 0;
+            let behind_bg = attrs & 0b1000_0000 != 0;
+            let palette = if attrs & 0b0001_0000 != 0 {
+                self.obp1
+            } else {
+                self.obp0
+            };
+
+            let mut row = self.ly as i16 - y;
+            if y_flip {
+                row = height - 1 - row;
+            }
+
+            let tile_data_addr = 0x8000 + tile_index as u16 * 16 + row as u16 * 2;
+            let low = self.vram[0][(tile_data_addr & (VRAM_SIZE - 1) as u16) as usize];
+            let high = self.vram[0][((tile_data_addr + 1) & (VRAM_SIZE - 1) as u16) as usize];
+
+            for col in 0..8i16 {
+                let bit = if x_flip { col } else { 7 - col };
+                let color = (((high >> bit) & 1) << 1) | ((low >> bit) & 1);
+                if color == 0 {
+                    continue;
+                }
+
+                let screen_x = x + col;
+                if screen_x < 0 || screen_x >= SCREEN_WIDTH as i16 {
+                    continue;
+                }
+                let screen_x = screen_x as usize;
+
+                if behind_bg && self.bg_color_index_line[screen_x] != 0 {
+                    continue;
+                }
+
+                let index = self.ly as usize * SCREEN_WIDTH as usize + screen_x;
+
+                self.framebuffer[index] = (palette >> (color * 2)) & 0b11;
+            }
+        }
+    }
+
+    /// CGB sprites drop the X-coordinate priority rule in favor of pure OAM order, use
+    /// their own tile VRAM bank and one of 8 CGB palettes, and respect the BG-over-OBJ
+    /// priority carried by both the tile attribute map and the sprite's own attribute byte.
+    fn render_sprites_cgb(&mut self) {
+        if !self.control.contains(Control::OBJ_ENABLE) {
+            return;
+        }
+
+        let height: i16 = if self.control.contains(Control::OBJ_SIZE) {
+            16
+        } else {
+            8
+        };
+
+        let sprites = self.sprites_on_line(height);
+
+        for &i in sprites.iter().rev() {
+            let entry = i * 4;
+            let y = self.oam[entry] as i16 - 16;
+            let x = self.oam[entry + 1] as i16 - 8;
+            let mut tile_index = self.oam[entry + 2];
+            let attrs = self.oam[entry + 3];
+
+            if height == 16 {
+                tile_index &= 0xfe;
+            }
+
+            let y_flip = attrs & 0b0100_0000 != 0;
+            let x_flip = attrs & 0b0010_0000 != 0;
+            let behind_bg = attrs & 0b1000_0000 != 0;
+            let palette = (attrs & 0b0000_0111) as usize;
+            let tile_bank = ((attrs >> 3) & 1) as usize;
+
+            let mut row = self.ly as i16 - y;
+            if y_flip {
+                row = height - 1 - row;
+            }
+
+            let tile_data_addr = 0x8000 + tile_index as u16 * 16 + row as u16 * 2;
+            let low = self.vram[tile_bank][(tile_data_addr & (VRAM_SIZE - 1) as u16) as usize];
+            let high = self.vram[tile_bank][((tile_data_addr + 1) & (VRAM_SIZE - 1) as u16) as usize];
+
+            for col in 0..8i16 {
+                let bit = if x_flip { col } else { 7 - col };
+                let color_index = (((high >> bit) & 1) << 1) | ((low >> bit) & 1);
+                if color_index == 0 {
+                    continue;
+                }
+
+                let screen_x = x + col;
+                if screen_x < 0 || screen_x >= SCREEN_WIDTH as i16 {
+                    continue;
+                }
+                let screen_x = screen_x as usize;
+
+                let hidden_by_bg = self.control.contains(Control::BG_ENABLE)
+                    && (behind_bg || self.bg_priority_line[screen_x])
+                    && self.bg_color_index_line[screen_x] != 0;
+                if hidden_by_bg {
+                    continue;
+                }
+
+                let index = self.ly as usize * SCREEN_WIDTH as usize + screen_x;
+                self.color_framebuffer[index] = self.obj_palette_color(palette, color_index as usize);
+            }
         }
     }
 
@@ -71,46 +536,60 @@ impl PPU {
         //info!("ly: {}", self.ly);
         self.clocks += tick;
 
+        if self.dma_active {
+            self.dma_clocks += tick;
+        }
+
         match self.mode {
             Mode::AccessOAM => {
                 if self.clocks >= 80 {
                     self.clocks -= 80;
                     self.mode = Mode::AccessVRAM;
-                    // render scanline
                 }
             }
             Mode::AccessVRAM => {
                 if self.clocks >= 172 {
                     self.clocks -= 172;
                     self.mode = Mode::HBlank;
-                    // interrupt
+                    self.render_scanline();
+                    self.render_sprites();
+                    if self.stat.contains(Stat::HBLANK_INTERRUPT) {
+                        self.fire_stat();
+                    }
                 }
             }
             Mode::HBlank => {
                 if self.clocks >= 204 {
                     self.clocks -= 204;
-                    self.ly = self.ly.wrapping_add(1);
+                    self.set_ly(self.ly.wrapping_add(1));
 
                     if self.ly >= SCREEN_HEIGHT {
                         self.mode = Mode::VBlank;
-                    // interrupt
+                        self.fire_vblank();
+                        if self.stat.contains(Stat::VBLANK_INTERRUPT) {
+                            self.fire_stat();
+                        }
                     } else {
                         self.mode = Mode::AccessOAM;
+                        if self.stat.contains(Stat::OAM_INTERRUPT) {
+                            self.fire_stat();
+                        }
                     }
-                    // interrupt
                 }
             }
             _ => {
                 if self.clocks >= 456 {
                     self.clocks -= 456;
-                    self.ly = self.ly.wrapping_add(1);
+                    self.set_ly(self.ly.wrapping_add(1));
 
                     if self.ly >= SCREEN_HEIGHT + 10 {
                         self.mode = Mode::AccessOAM;
-                        self.ly = 0;
-                        // interrupt
+                        self.set_ly(0);
+                        self.window_line = 0;
+                        if self.stat.contains(Stat::OAM_INTERRUPT) {
+                            self.fire_stat();
+                        }
                     }
-                    // interrupt
                 }
             }
         }
@@ -122,13 +601,44 @@ impl PPU {
                 if self.mode == Mode::AccessVRAM {
                     return 0xff;
                 }
-                self.vram[(addr & (VRAM_SIZE - 1) as u16) as usize]
+                let bank = if self.cgb { (self.vbk & 1) as usize } else { 0 };
+                self.vram[bank][(addr & (VRAM_SIZE - 1) as u16) as usize]
+            }
+            0xfe00..=0xfe9f => {
+                if self.mode == Mode::AccessOAM || self.mode == Mode::AccessVRAM {
+                    return 0xff;
+                }
+                self.oam[(addr - 0xfe00) as usize]
             }
             0xff40 => self.control.bits,
+            0xff41 => {
+                let mode_bits = match self.mode {
+                    Mode::HBlank => Stat::HBLANK_MODE,
+                    Mode::VBlank => Stat::VBLANK_MODE,
+                    Mode::AccessOAM => Stat::ACCESS_OAM_MODE,
+                    Mode::AccessVRAM => Stat::ACCESS_VRAM_MODE,
+                };
+                let mut bits = (self.stat.bits & !0b0000_0111) | mode_bits.bits;
+                if self.lyc_flag {
+                    bits |= Stat::LYC_FLAG.bits;
+                }
+                bits
+            }
             0xff42 => self.scy,
             0xff43 => self.scx,
             0xff44 => self.ly,
+            0xff45 => self.lyc,
+            0xff46 => self.dma,
             0xff47 => self.bgp,
+            0xff48 => self.obp0,
+            0xff49 => self.obp1,
+            0xff4a => self.wy,
+            0xff4b => self.wx,
+            0xff4f if self.cgb => 0xfe | self.vbk,
+            0xff68 if self.cgb => self.bg_palette_index,
+            0xff69 if self.cgb => self.bg_palette_ram[(self.bg_palette_index & 0x3f) as usize],
+            0xff6a if self.cgb => self.obj_palette_index,
+            0xff6b if self.cgb => self.obj_palette_ram[(self.obj_palette_index & 0x3f) as usize],
             _ => 0xff,
         }
     }
@@ -139,7 +649,14 @@ impl PPU {
                 if self.mode == Mode::AccessVRAM {
                     return;
                 }
-                self.vram[(addr & (VRAM_SIZE - 1) as u16) as usize] = v;
+                let bank = if self.cgb { (self.vbk & 1) as usize } else { 0 };
+                self.vram[bank][(addr & (VRAM_SIZE - 1) as u16) as usize] = v;
+            }
+            0xfe00..=0xfe9f => {
+                if self.mode == Mode::AccessOAM || self.mode == Mode::AccessVRAM {
+                    return;
+                }
+                self.oam[(addr - 0xfe00) as usize] = v;
             }
             0xff40 => {
                 let val = Control::from_bits_truncate(v);
@@ -152,14 +669,71 @@ impl PPU {
                         Stat::HBLANK_MODE
                     };
                     self.stat.insert(mode);
-                    // interrupt
+                    if val.contains(Control::LCD_ENABLE) && self.stat.contains(Stat::OAM_INTERRUPT) {
+                        self.fire_stat();
+                    }
                 }
                 self.control = val;
             }
+            0xff41 => {
+                let writable = Stat::LYC_INTERRUPT
+                    | Stat::OAM_INTERRUPT
+                    | Stat::VBLANK_INTERRUPT
+                    | Stat::HBLANK_INTERRUPT;
+                self.stat = (self.stat & !writable) | (Stat::from_bits_truncate(v) & writable);
+            }
             0xff42 => self.scy = v,
             0xff43 => self.scx = v,
             0xff44 => (), // read only
+            0xff45 => {
+                self.lyc = v;
+                self.set_ly(self.ly);
+            }
+            0xff46 => {
+                self.dma = v;
+                self.dma_source = (v as u16) << 8;
+                self.dma_progress = 0;
+                self.dma_active = true;
+                self.dma_clocks = 0;
+            }
             0xff47 => self.bgp = v,
+            0xff48 => self.obp0 = v,
+            0xff49 => self.obp1 = v,
+            0xff4a => self.wy = v,
+            0xff4b => self.wx = v,
+            0xff4f => {
+                if self.cgb {
+                    self.vbk = v & 1;
+                }
+            }
+            0xff68 => {
+                if self.cgb {
+                    self.bg_palette_index = v & 0xbf;
+                }
+            }
+            0xff69 => {
+                if self.cgb {
+                    let index = (self.bg_palette_index & 0x3f) as usize;
+                    self.bg_palette_ram[index] = v;
+                    if self.bg_palette_index & 0x80 != 0 {
+                        self.bg_palette_index = 0x80 | ((index as u8 + 1) & 0x3f);
+                    }
+                }
+            }
+            0xff6a => {
+                if self.cgb {
+                    self.obj_palette_index = v & 0xbf;
+                }
+            }
+            0xff6b => {
+                if self.cgb {
+                    let index = (self.obj_palette_index & 0x3f) as usize;
+                    self.obj_palette_ram[index] = v;
+                    if self.obj_palette_index & 0x80 != 0 {
+                        self.obj_palette_index = 0x80 | ((index as u8 + 1) & 0x3f);
+                    }
+                }
+            }
             _ => unreachable!("write: not support address: 0x{:04x}", addr),
         }
     }